@@ -17,7 +17,7 @@
 
 use std::convert::{From, Into};
 
-use bincode::{serialize, Infinite};
+use bincode::{serialize, deserialize, Infinite};
 use crate::types::{H256, Address};
 use crate::core::params::PrivateKey;
 use crypto::{pubkey_to_address, Signature, Sign, SIGNATURE_BYTES_LEN, Signer};
@@ -28,12 +28,30 @@ use hashable::Hashable;
 use libproto::blockchain::{Block, Proof as ProtoProof, ProofType, BlockTxs};
 use libproto::router::{MsgType, RoutingKey, SubModules};
 use libproto::{TryFrom, TryInto, Message, auth, auth::VerifyBlockResp};
-use std::collections::{HashMap, VecDeque};
+use libproto::snapshot::{Cmd, Resp as SnapshotAck, SnapshotResp};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::VartimeMultiscalarMul;
+use rand::rngs::OsRng;
 
 use engine::{unix_now, AsMillis};
 
 pub type PubType = (String, Vec<u8>);
 
+/// A committed-block record queued for an external relayer, e.g. a foreign
+/// chain's light client verifying CITA finality from the precommit
+/// signatures alone.
+#[derive(Clone)]
+pub struct RelayRecord {
+    pub height: u64,
+    pub block_hash: H256,
+    pub proof: ProtoProof,
+}
+
 pub enum BridgeMsg{
     CheckBlockReq(Vec<u8>, u64),
     CheckBlockResp(bool),
@@ -47,22 +65,33 @@ pub enum BridgeMsg{
     SignResp(Option<BftSig>),
 }
 
+/// Relay records flow Processor -> relayer and confirmed heights flow
+/// relayer -> Processor, so these are two separate channels rather than one
+/// shared message type both ends of which Processor would otherwise own.
+/// `p2l`'s matching `Receiver<RelayRecord>` belongs to the external relayer,
+/// and `l4p`'s matching `Sender<u64>` is that same relayer's -- Processor
+/// only ever holds the Processor-side half of each, so it can't loop a
+/// relay record back to itself.
 pub struct Processor {
     p2b_b: Sender<BridgeMsg>,
     p2b_f: Sender<BridgeMsg>,
     p2b_s: Sender<BridgeMsg>,
     p2b_t: Sender<BridgeMsg>,
     p2r: Sender<PubType>,
+    p2l: Sender<RelayRecord>,
     p4b: Receiver<BridgeMsg>,
     p4r: Receiver<PubType>,
+    l4p: Receiver<u64>,
     bft_actuator: BftActuator,
 
     signer: PrivateKey,
     address: BftAddr,
+    scheme: Arc<dyn SignatureScheme>,
 
     proof: HashMap<u64, Proof>,
     pre_hash: HashMap<u64, H256>,
     version:  HashMap<u64, u32>,
+    authority_lists: HashMap<u64, Vec<Node>>,
 
     get_block_reqs: VecDeque<u64>,
     check_tx_reqs: VecDeque<(u64, u64)>,
@@ -70,6 +99,13 @@ pub struct Processor {
     get_block_resps: HashMap<u64, BlockTxs>,
     check_tx_resps: HashMap<(u64, u64), VerifyBlockResp>,
 
+    relay_queue: VecDeque<RelayRecord>,
+    relay_seen: HashSet<H256>,
+    relay_height: u64,
+    relay_confirmed: u64,
+
+    current_height: u64,
+
     is_snapshot: bool,
     is_cleared: bool,
 }
@@ -81,21 +117,27 @@ impl Processor{
         loop{
             let mut get_rab_msg = Err(RecvError);
             let mut get_bridge_msg = Err(RecvError);
+            let mut get_relay_confirm = Err(RecvError);
 
             select! {
                 recv(self.p4r) -> msg => get_rab_msg = msg,
                 recv(self.p4b) -> msg => get_bridge_msg = msg,
+                recv(self.l4p) -> msg => get_relay_confirm = msg,
             }
 
             if let Ok((key, body)) = get_rab_msg {
                 let rt_key = RoutingKey::from(&key);
                 match rt_key {
                     routing_key!(Net >> CompactSignedProposal) => {
-                        self.bft_actuator.send(BftMsg::Proposal(body)).unwrap();
+                        if !self.is_snapshot {
+                            self.bft_actuator.send(BftMsg::Proposal(body)).unwrap();
+                        }
                     }
 
                     routing_key!(Net >> RawBytes) => {
-                        self.bft_actuator.send(BftMsg::Vote(body)).unwrap();
+                        if !self.is_snapshot {
+                            self.bft_actuator.send(BftMsg::Vote(body)).unwrap();
+                        }
                     }
 
                     routing_key!(Chain >> RichStatus) => {
@@ -116,11 +158,96 @@ impl Processor{
                     }
 
                     routing_key!(Auth >> VerifyBlockResp) => {
-//                        self.resp_sender.send((key, body)).unwrap();
+                        let mut msg = Message::try_from(&body[..]).unwrap();
+                        let resp = msg.take_verify_block_resp().unwrap();
+                        let v_height = resp.get_height();
+                        let v_round = resp.get_round();
+                        if v_height >= self.current_height {
+                            self.check_tx_resps.entry((v_height, v_round)).or_insert(resp);
+                        }
+                        self.check_transaction();
                     }
 
                     routing_key!(Snapshot >> SnapshotReq) => {
-                        // TODO
+                        let mut msg = Message::try_from(&body[..]).unwrap();
+                        let req = msg.take_snapshot_req().unwrap();
+                        match req.get_cmd() {
+                            Cmd::Begin => {
+                                self.is_snapshot = true;
+                                // Every queued request has a synchronous caller blocking on
+                                // exactly one reply (see `drain_resolved_check_tx`) -- a bare
+                                // `clear()` would leave those callers hanging forever.
+                                for _ in 0..self.get_block_reqs.len() {
+                                    self.p2b_f.send(BridgeMsg::GetBlockResp(None)).unwrap();
+                                }
+                                self.get_block_reqs.clear();
+                                for _ in 0..self.check_tx_reqs.len() {
+                                    self.p2b_t.send(BridgeMsg::CheckTxResp(false)).unwrap();
+                                }
+                                self.check_tx_reqs.clear();
+                                self.ack_snapshot(SnapshotAck::BeginResp, req.get_end_height());
+                            }
+
+                            Cmd::Clear => {
+                                self.proof.clear();
+                                self.pre_hash.clear();
+                                self.version.clear();
+                                self.get_block_resps.clear();
+                                self.check_tx_resps.clear();
+                                self.is_cleared = true;
+                                self.ack_snapshot(SnapshotAck::ClearAck, req.get_end_height());
+                            }
+
+                            Cmd::Restore => {
+                                let height = req.get_end_height();
+                                let mut restored_proof: Option<BftProof> = None;
+                                // Only trust a supplied proof once `Cmd::Clear` has actually
+                                // wiped `self.proof` -- a `Restore` arriving without a prior
+                                // `Clear` isn't part of a real snapshot sequence, so there's
+                                // nothing here worth overwriting.
+                                if self.is_cleared {
+                                    let proof_bytes = req.get_proof();
+                                    if !proof_bytes.is_empty() {
+                                        if let Ok(bft_proof) = deserialize::<BftProof>(proof_bytes) {
+                                            // A proof embedded for a different height than the
+                                            // one being restored would make every later
+                                            // `check_block` at `height` fail `verify_bft_proof`'s
+                                            // height check, so it's not worth storing.
+                                            if bft_proof.height as u64 == height {
+                                                self.proof.entry(height).or_insert_with(|| from_bft_proof(&bft_proof));
+                                                restored_proof = Some(bft_proof);
+                                            }
+                                        }
+                                    }
+                                }
+                                // `authority_lists` is only ever populated from a live
+                                // `RichStatus`, so a node resuming from a snapshot has nothing
+                                // cached for `height`. Fall back to the voters named in the
+                                // restored proof itself -- an incomplete validator set (only
+                                // those who precommitted) still lets `check_block` validate
+                                // something, unlike the empty list it would see otherwise.
+                                let authority_list = self.authority_lists.entry(height).or_insert_with(|| {
+                                    restored_proof.map(|proof| {
+                                        proof.commits.keys().map(|addr| Node {
+                                            address: addr.clone(),
+                                            proposal_weight: 1,
+                                            vote_weight: 1,
+                                        }).collect()
+                                    }).unwrap_or_default()
+                                }).clone();
+                                let status = Status{
+                                    height,
+                                    interval: None,
+                                    authority_list,
+                                };
+                                self.bft_actuator.send(BftMsg::Status(status)).unwrap();
+                                self.is_snapshot = false;
+                                self.is_cleared = false;
+                                self.ack_snapshot(SnapshotAck::RestoreResp, height);
+                            }
+
+                            _ => {}
+                        }
                     }
 
                     _ => {}
@@ -146,6 +273,7 @@ impl Processor{
                             ))
                             .unwrap();
                         self.check_tx_reqs.push_back((height, round));
+                        self.check_transaction();
                     }
 
                     BridgeMsg::SignReq(hash) => {
@@ -163,6 +291,13 @@ impl Processor{
                     _ => {}
                 }
             }
+
+            if let Ok(height) = get_relay_confirm {
+                if height > self.relay_confirmed {
+                    self.relay_confirmed = height;
+                }
+                self.relay_queue.retain(|record| record.height > height);
+            }
         }
     }
 
@@ -171,10 +306,13 @@ impl Processor{
                p2b_s: Sender<BridgeMsg>,
                p2b_t: Sender<BridgeMsg>,
                p2r: Sender<PubType>,
+               p2l: Sender<RelayRecord>,
                p4b: Receiver<BridgeMsg>,
                p4r: Receiver<PubType>,
+               l4p: Receiver<u64>,
                bft_actuator: BftActuator,
-               pk: PrivateKey) -> Self{
+               pk: PrivateKey,
+               scheme: Arc<dyn SignatureScheme>) -> Self{
         let signer = Signer::from(pk.signer.clone());
         let address = signer.address.to_vec();
         Processor{
@@ -183,43 +321,91 @@ impl Processor{
             p2b_s,
             p2b_t,
             p2r,
+            p2l,
             p4b,
             p4r,
+            l4p,
             bft_actuator,
             signer: pk,
             address,
+            scheme,
             proof: HashMap::new(),
             pre_hash: HashMap::new(),
             version: HashMap::new(),
+            authority_lists: HashMap::new(),
             get_block_reqs: VecDeque::new(),
             check_tx_reqs: VecDeque::new(),
             get_block_resps: HashMap::new(),
             check_tx_resps: HashMap::new(),
+            relay_queue: VecDeque::new(),
+            relay_seen: HashSet::new(),
+            relay_height: 0,
+            relay_confirmed: 0,
+            current_height: 0,
             is_snapshot: false,
             is_cleared: false,
         }
     }
 
-    fn check_block(&self, _block: &[u8], _height: u64) -> bool{
-        true
-    }
-    /// A function to check signature.
-    fn check_transaction(&mut self, _block: &[u8], _height: u64, _round: u64) -> bool{
-//        loop{
-//            let (_, body) = self.resp_receiver.recv().unwrap();
-//            let mut msg = Message::try_from(body).unwrap();
-//            let resp = msg.take_verify_block_resp().unwrap();
-//            let block = resp.get_block();
-//            let v_height = resp.get_height();
-//            let v_round = resp.get_round();
-//            if v_height == height && v_round == round {
-//
-//            } else {
-//
-//            }
-//        }
+    fn check_block(&self, block: &[u8], height: u64) -> bool{
+        let blk = match Block::try_from(block) {
+            Ok(blk) => blk,
+            Err(_) => return false,
+        };
+        if blk.get_header().get_height() != height {
+            return false;
+        }
 
-        false
+        let proto_proof = blk.get_header().get_proof();
+        if proto_proof.get_field_type() != ProofType::Bft {
+            return false;
+        }
+        let bft_proof: BftProof = match deserialize(proto_proof.get_content()) {
+            Ok(proof) => proof,
+            Err(_) => return false,
+        };
+
+        let authority_list = match self.authority_lists.get(&height) {
+            Some(list) => list,
+            None => return false,
+        };
+
+        // The precommits vote for the block as it was proposed, i.e. before
+        // the proof itself was attached to the header: re-derive that
+        // pre-proof hash the same way so `bft_proof.proposal` lines up with
+        // it. This assumes the proposer hashes the same "full `Block` with
+        // `proof` cleared" encoding verified here; `test_verify_bft_proof_*`
+        // below only pins this function's own internal consistency; it can't
+        // confirm that against the live proposer path, which isn't part of
+        // this file.
+        let mut unsigned_block = blk.clone();
+        unsigned_block.mut_header().clear_proof();
+        let block_bytes: Vec<u8> = match unsigned_block.try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => return false,
+        };
+        let block_hash = block_bytes.crypt_hash();
+
+        verify_bft_proof(
+            &bft_proof,
+            &block_hash,
+            height,
+            blk.get_header().get_round() as usize,
+            authority_list,
+            &*self.scheme,
+        )
+    }
+    /// Match any buffered `VerifyBlockResp` against the front of
+    /// `check_tx_reqs`, replying over `p2b_t` once the in-order response is
+    /// available. Stale requests (for heights the consensus has already
+    /// moved past) are rejected with a `false` reply -- never dropped
+    /// silently -- since `BftSupport::check_transaction` blocks on exactly
+    /// one `CheckTxResp` per request.
+    fn check_transaction(&mut self){
+        let responses = drain_resolved_check_tx(&mut self.check_tx_reqs, &mut self.check_tx_resps, self.current_height);
+        for pass in responses {
+            self.p2b_t.send(BridgeMsg::CheckTxResp(pass)).unwrap();
+        }
     }
     /// A funciton to transmit messages.
     fn transmit(&self, msg: BftMsg){
@@ -246,8 +432,40 @@ impl Processor{
         }
     }
     /// A function to commit the proposal.
-    fn commit(&mut self, _commit: Commit){
+    fn commit(&mut self, commit: Commit){
+        let height = commit.height;
+        if height < self.relay_height {
+            return;
+        }
+        let block_hash = commit.block.crypt_hash();
+        if !self.relay_seen.insert(block_hash) {
+            return;
+        }
+        self.relay_height = height + 1;
 
+        let proof = to_bft_proof(&commit.proof);
+        let record = RelayRecord{
+            height,
+            block_hash,
+            proof,
+        };
+        self.relay_queue.push_back(record.clone());
+        self.p2l.send(record).unwrap();
+    }
+
+    /// Ack a stage of the snapshot control flow so the snapshot coordinator
+    /// knows the BFT engine has quiesced.
+    fn ack_snapshot(&self, resp: SnapshotAck, height: u64){
+        let mut snapshot_resp = SnapshotResp::new();
+        snapshot_resp.set_resp(resp);
+        snapshot_resp.set_height(height);
+        let msg: Message = snapshot_resp.into();
+        self.p2r
+            .send((
+                routing_key!(Consensus >> SnapshotResp).into(),
+                msg.try_into().unwrap(),
+            ))
+            .unwrap();
     }
 
     fn get_block (&self, height: u64, block_txs: &BlockTxs) -> Option<Vec<u8>>{
@@ -274,10 +492,7 @@ impl Processor{
     }
 
     fn sign(&self, hash: &[u8]) -> Option<BftSig>{
-        if let Ok(signature) = Signature::sign(&self.signer.signer, &H256::from(hash)){
-            return Some((&signature.0).to_vec());
-        }
-        None
+        self.scheme.sign(&self.signer, &H256::from(hash))
     }
 
     fn extract_status(&mut self, body: &[u8]) -> Status{
@@ -289,6 +504,10 @@ impl Processor{
         self.pre_hash.entry(height).or_insert(pre_hash);
         self.version.entry(height).or_insert(status.version);
 
+        self.current_height = height;
+        self.check_transaction();
+        self.check_tx_resps.retain(|&(resp_height, _), _| resp_height >= height);
+
         let mut map = HashMap::new();
         status.get_nodes().iter().for_each(|node| {
             let counter = map.entry(node.to_vec()).or_insert(0u32);
@@ -303,6 +522,8 @@ impl Processor{
             }
         }).collect();
 
+        self.authority_lists.entry(height).or_insert_with(|| authority_list.clone());
+
         Status{
             height,
             interval: Some(status.interval),
@@ -317,6 +538,7 @@ pub struct BftBridge {
     b4p_f: Receiver<BridgeMsg>,
     b4p_s: Receiver<BridgeMsg>,
     b4p_t: Receiver<BridgeMsg>,
+    scheme: Arc<dyn SignatureScheme>,
 }
 
 impl BftBridge {
@@ -324,7 +546,8 @@ impl BftBridge {
                b4p_b: Receiver<BridgeMsg>,
                b4p_f: Receiver<BridgeMsg>,
                b4p_s: Receiver<BridgeMsg>,
-               b4p_t: Receiver<BridgeMsg>
+               b4p_t: Receiver<BridgeMsg>,
+               scheme: Arc<dyn SignatureScheme>,
     ) -> Self{
         BftBridge{
             b2p,
@@ -332,6 +555,7 @@ impl BftBridge {
             b4p_f,
             b4p_s,
             b4p_t,
+            scheme,
         }
     }
 }
@@ -378,19 +602,296 @@ impl BftSupport for BftBridge {
     }
 
     fn check_sig(&self, signature: &[u8], hash: &[u8]) -> Option<BftAddr>{
+        self.scheme.check_sig(signature, &H256::from(hash)).map(|address| address.to_vec())
+    }
+
+    fn crypt_hash(&self, msg: &[u8]) -> Vec<u8>{
+        msg.to_vec().crypt_hash().to_vec()
+    }
+}
+
+/// Recover the signing address from a signature over `hash`, sharing the
+/// recovery logic used by both `check_sig` and proof verification.
+fn recover_signer(signature: &Signature, hash: &H256) -> Option<Address> {
+    signature.recover(hash).ok().map(|pubkey| pubkey_to_address(&pubkey))
+}
+
+/// Pop every `check_tx_reqs` entry that can be resolved against
+/// `current_height`/`check_tx_resps`, in order, and return the pass/fail
+/// reply each one must receive. A request for a height the consensus has
+/// already moved past is popped with a `false` reply rather than silently
+/// dropped -- its synchronous caller is still waiting on exactly one
+/// `CheckTxResp`. Stops at the first still-pending request so responses
+/// stay in order and a duplicate reply can't resolve the wrong request.
+fn drain_resolved_check_tx(
+    check_tx_reqs: &mut VecDeque<(u64, u64)>,
+    check_tx_resps: &mut HashMap<(u64, u64), VerifyBlockResp>,
+    current_height: u64,
+) -> Vec<bool> {
+    let mut replies = Vec::new();
+    while let Some(&(height, round)) = check_tx_reqs.front() {
+        if height < current_height {
+            check_tx_reqs.pop_front();
+            check_tx_resps.remove(&(height, round));
+            replies.push(false);
+            continue;
+        }
+        match check_tx_resps.remove(&(height, round)) {
+            Some(resp) => {
+                replies.push(resp.get_pass());
+                check_tx_reqs.pop_front();
+            }
+            None => break,
+        }
+    }
+    replies
+}
+
+/// Check that `bft_proof` actually finalizes `block_hash` at `block_height`
+/// / `block_round`, and that the accumulated precommit weight clears 2/3 of
+/// `authority_list`'s total. A proof that checks out for a different block
+/// (same height/round, different hash) must not verify here -- otherwise a
+/// valid proof could be transplanted onto a malicious block.
+fn verify_bft_proof(
+    bft_proof: &BftProof,
+    block_hash: &H256,
+    block_height: u64,
+    block_round: usize,
+    authority_list: &[Node],
+    scheme: &dyn SignatureScheme,
+) -> bool {
+    if bft_proof.height != block_height as usize {
+        return false;
+    }
+    if bft_proof.round != block_round {
+        return false;
+    }
+    if &bft_proof.proposal != block_hash {
+        return false;
+    }
+
+    let total_weight: u64 = authority_list.iter().map(|node| u64::from(node.vote_weight)).sum();
+    if total_weight == 0 {
+        return false;
+    }
+
+    let verified = scheme.verify_precommits(&bft_proof.commits, &bft_proof.proposal);
+    let weight: u64 = verified.iter()
+        .filter_map(|addr| authority_list.iter().find(|node| node.address == addr.to_vec()))
+        .map(|node| u64::from(node.vote_weight))
+        .sum();
+
+    weight * 3 > total_weight * 2
+}
+
+/// A pluggable signature scheme for consensus votes. `EcdsaScheme` is the
+/// default, recovering one signer per curve operation. `SchnorrScheme`
+/// trades that single-signature recovery for the ability to verify an
+/// entire proof's precommit votes in one batched check.
+pub trait SignatureScheme: Send + Sync {
+    fn sign(&self, signer: &PrivateKey, hash: &H256) -> Option<BftSig>;
+
+    fn check_sig(&self, signature: &[u8], hash: &H256) -> Option<Address>;
+
+    /// Verify every precommit vote in `commits` against `hash` and return
+    /// the addresses whose vote should count. Schemes that support batch
+    /// verification should do a single batched check on the happy path,
+    /// falling back to per-signature verification -- so the offending
+    /// validator can be named and dropped -- only when the batch check
+    /// fails.
+    fn verify_precommits(&self, commits: &HashMap<Address, Signature>, hash: &H256) -> HashSet<Address>;
+}
+
+/// The scheme a node uses when nothing more specific is configured.
+/// `EcdsaScheme` is the only scheme `Processor::new`'s `pk: PrivateKey` can
+/// select on its own -- `SchnorrScheme` additionally needs a verification-key
+/// registry for the rest of the authority list, which has to come from node
+/// config rather than being derived from a single local key.
+pub fn default_scheme(_pk: &PrivateKey) -> Arc<dyn SignatureScheme> {
+    Arc::new(EcdsaScheme)
+}
+
+/// The original secp256k1 ECDSA recovery scheme.
+pub struct EcdsaScheme;
+
+impl SignatureScheme for EcdsaScheme {
+    fn sign(&self, signer: &PrivateKey, hash: &H256) -> Option<BftSig> {
+        Signature::sign(&signer.signer, hash).ok().map(|signature| (&signature.0).to_vec())
+    }
+
+    fn check_sig(&self, signature: &[u8], hash: &H256) -> Option<Address> {
         if signature.len() != SIGNATURE_BYTES_LEN {
             return None;
         }
-        let signature = Signature::from(signature);
-        if let Ok(pubkey) = signature.recover(&H256::from(hash)) {
-            let address = pubkey_to_address(&pubkey);
-            return Some(address.to_vec());
+        recover_signer(&Signature::from(signature), hash)
+    }
+
+    fn verify_precommits(&self, commits: &HashMap<Address, Signature>, hash: &H256) -> HashSet<Address> {
+        commits.iter()
+            .filter_map(|(addr, sig)| {
+                let recovered = recover_signer(sig, hash)?;
+                if &recovered == addr {
+                    Some(recovered)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Byte length of an on-wire Schnorr vote: `R (32) || s (32)`, zero-padded
+/// out to `SIGNATURE_BYTES_LEN` so it fits the fixed-size `crypto::Signature`
+/// that `BftProof.commits` -- and therefore the block header -- actually
+/// stores. `vk` deliberately isn't carried on the wire: at 96 bytes it would
+/// overflow that fixed slot, and `SchnorrScheme` already has to resolve
+/// addresses through `verification_keys` instead of deriving them, so a vote
+/// need only carry enough to check against the vk its address maps to.
+const SCHNORR_SIG_LEN: usize = SIGNATURE_BYTES_LEN;
+
+/// Schnorr-over-Ristretto scheme. Unlike `EcdsaScheme`, a Ristretto
+/// verification key cannot be hashed back into the chain's ECDSA-derived
+/// node addresses, so this scheme carries its own `address -> vk` registry
+/// supplied at construction time (e.g. from node config) instead of
+/// deriving addresses on the fly. A node that also votes under this scheme
+/// is configured with its own `secret` scalar so `sign` can produce
+/// precommits; a verify-only node (no local Schnorr key) is constructed
+/// with `secret: None`.
+pub struct SchnorrScheme {
+    secret: Option<Scalar>,
+    verification_keys: HashMap<Address, RistrettoPoint>,
+}
+
+impl SchnorrScheme {
+    /// Build a verify-only scheme from a registry of known verification keys.
+    pub fn new(verification_keys: HashMap<Address, RistrettoPoint>) -> Self {
+        SchnorrScheme {
+            secret: None,
+            verification_keys,
         }
-        None
     }
 
-    fn crypt_hash(&self, msg: &[u8]) -> Vec<u8>{
-        msg.to_vec().crypt_hash().to_vec()
+    /// Build a scheme that can also sign, using `secret` as this node's
+    /// Schnorr key. `secret`'s own address must be present in
+    /// `verification_keys` for other nodes to recover its votes.
+    pub fn with_secret(secret: Scalar, verification_keys: HashMap<Address, RistrettoPoint>) -> Self {
+        SchnorrScheme {
+            secret: Some(secret),
+            verification_keys,
+        }
+    }
+
+    fn challenge(r: &RistrettoPoint, vk: &RistrettoPoint, hash: &H256) -> Scalar {
+        let mut data = Vec::with_capacity(96);
+        data.extend_from_slice(r.compress().as_bytes());
+        data.extend_from_slice(vk.compress().as_bytes());
+        data.extend_from_slice(&hash.0);
+        Scalar::from_bytes_mod_order(data.crypt_hash().0)
+    }
+
+    /// Pull `(R, s)` out of a vote's raw wire bytes; `None` if the length
+    /// doesn't match `SCHNORR_SIG_LEN` or either half doesn't decode to a
+    /// valid point/scalar.
+    fn decode_r_s(bytes: &[u8]) -> Option<(RistrettoPoint, Scalar)> {
+        if bytes.len() != SCHNORR_SIG_LEN {
+            return None;
+        }
+        let r = CompressedRistretto::from_slice(&bytes[0..32]).decompress()?;
+        let mut s_bytes = [0u8; 32];
+        s_bytes.copy_from_slice(&bytes[32..64]);
+        let s = Scalar::from_canonical_bytes(s_bytes)?;
+        Some((r, s))
+    }
+
+    /// Decode a vote already matched to its address (e.g. a `BftProof.commits`
+    /// entry, keyed by address) against that address's registered vk.
+    fn decode_vote(&self, addr: &Address, sig: &Signature) -> Option<(Address, RistrettoPoint, RistrettoPoint, Scalar)> {
+        let vk = self.verification_keys.get(addr)?;
+        let (r, s) = Self::decode_r_s(&sig.0)?;
+        Some((addr.clone(), *vk, r, s))
+    }
+
+    /// Decode a vote with no address context (e.g. a single in-flight vote
+    /// message) by trying every registered vk until one validates. Linear in
+    /// the size of the validator set, unlike `EcdsaScheme`, which recovers
+    /// the signer with a single curve recovery.
+    fn find_signer(&self, bytes: &[u8], hash: &H256) -> Option<(Address, RistrettoPoint, RistrettoPoint, Scalar)> {
+        let (r, s) = Self::decode_r_s(bytes)?;
+        self.verification_keys.iter()
+            .find(|(_, vk)| Self::verify_one(vk, &r, &s, hash))
+            .map(|(addr, vk)| (addr.clone(), *vk, r, s))
+    }
+
+    fn verify_one(vk: &RistrettoPoint, r: &RistrettoPoint, s: &Scalar, hash: &H256) -> bool {
+        let c = Self::challenge(r, vk, hash);
+        &RISTRETTO_BASEPOINT_TABLE * s == r + vk * c
+    }
+}
+
+impl SignatureScheme for SchnorrScheme {
+    fn sign(&self, _signer: &PrivateKey, hash: &H256) -> Option<BftSig> {
+        let secret = self.secret?;
+        let vk = &secret * &RISTRETTO_BASEPOINT_TABLE;
+        let nonce = Scalar::random(&mut OsRng);
+        let r = &nonce * &RISTRETTO_BASEPOINT_TABLE;
+        let c = Self::challenge(&r, &vk, hash);
+        let s = nonce + c * secret;
+
+        let mut bytes = Vec::with_capacity(SCHNORR_SIG_LEN);
+        bytes.extend_from_slice(r.compress().as_bytes());
+        bytes.extend_from_slice(s.as_bytes());
+        bytes.resize(SCHNORR_SIG_LEN, 0);
+        Some(bytes)
+    }
+
+    fn check_sig(&self, signature: &[u8], hash: &H256) -> Option<Address> {
+        self.find_signer(signature, hash).map(|(addr, ..)| addr)
+    }
+
+    fn verify_precommits(&self, commits: &HashMap<Address, Signature>, hash: &H256) -> HashSet<Address> {
+        let votes: Vec<(Address, RistrettoPoint, RistrettoPoint, Scalar)> = commits.iter()
+            .filter_map(|(addr, sig)| self.decode_vote(addr, sig))
+            .collect();
+        if votes.is_empty() {
+            return HashSet::new();
+        }
+
+        // The z_i batching scalars must be unpredictable to and independent
+        // of the signer-controlled (R_i, s_i, vk_i) values: deriving them
+        // from the vote data itself would let a signer pick two invalid
+        // votes whose error terms cancel out, passing the batch check.
+        // Sample them from a CSPRNG instead, per the batch-verification
+        // requirement.
+        let mut csprng = OsRng;
+        let zs: Vec<Scalar> = (0..votes.len()).map(|_| Scalar::random(&mut csprng)).collect();
+
+        let lhs_scalar: Scalar = votes.iter().zip(zs.iter())
+            .map(|((_, _, _, s), z)| z * s)
+            .sum();
+        let lhs = &RISTRETTO_BASEPOINT_TABLE * &lhs_scalar;
+
+        let r_points = votes.iter().map(|(_, _, r, _)| *r);
+        let vk_points = votes.iter().map(|(_, vk, _, _)| *vk);
+        let r_scalars = zs.iter().cloned();
+        let vk_scalars = votes.iter().zip(zs.iter())
+            .map(|((_, vk, r, _), z)| z * Self::challenge(r, vk, hash));
+
+        let rhs = RistrettoPoint::vartime_multiscalar_mul(
+            r_scalars.chain(vk_scalars),
+            r_points.chain(vk_points),
+        );
+
+        if lhs == rhs {
+            return votes.into_iter().map(|(addr, ..)| addr).collect();
+        }
+
+        // The batch equation failed: fall back to per-signature
+        // verification so the offending validator can be dropped while the
+        // rest of the proof is still counted.
+        votes.into_iter()
+            .filter(|(_, vk, r, s)| Self::verify_one(vk, r, s, hash))
+            .map(|(addr, ..)| addr)
+            .collect()
     }
 }
 
@@ -412,6 +913,21 @@ fn to_bft_proof(proof: &Proof) -> ProtoProof {
     proof
 }
 
+/// Inverse of `to_bft_proof`: rebuild the `bft::Proof` a restored height
+/// needs for `get_block` out of the embedded `BftProof` a snapshot restore
+/// request supplies.
+fn from_bft_proof(bft_proof: &BftProof) -> Proof {
+    let precommit_votes: HashMap<Vec<u8>, Vec<u8>> = bft_proof.commits.iter()
+        .map(|(addr, sig)| (addr.to_vec(), (&sig.0).to_vec()))
+        .collect();
+    Proof {
+        block_hash: bft_proof.proposal.0.to_vec(),
+        height: bft_proof.height as u64,
+        round: bft_proof.round as u64,
+        precommit_votes,
+    }
+}
+
 fn get_block_req_msg (block: &[u8], height: u64, round: u64) -> Message{
     let mut msg = Message::try_from(block).unwrap();
     let origin = msg.get_origin();
@@ -454,4 +970,143 @@ mod test {
 
         println!("{:?}", authority_list);
     }
+
+    #[test]
+    fn test_verify_bft_proof_rejects_proof_for_wrong_block() {
+        let authority_list = vec![Node {
+            address: vec![1u8; 20],
+            proposal_weight: 1,
+            vote_weight: 100,
+        }];
+        // A proof that is otherwise well-formed for height 10, round 0, but
+        // commits to a different block hash than the one being checked.
+        let mut commits = HashMap::new();
+        commits.insert(Address::from(&[1u8; 20][..]), Signature::from(&[0u8; SIGNATURE_BYTES_LEN][..]));
+        let bft_proof = BftProof {
+            proposal: H256::from(&[9u8; 32][..]),
+            height: 10,
+            round: 0,
+            commits,
+        };
+        let scheme = AlwaysValidScheme;
+        let block_hash = H256::from(&[1u8; 32][..]);
+
+        assert!(!verify_bft_proof(&bft_proof, &block_hash, 10, 0, &authority_list, &scheme));
+    }
+
+    /// A vote-verification stub that trusts every commit unconditionally, so
+    /// a test can isolate `verify_bft_proof`'s height/round/hash-binding
+    /// gates from actual signature checking.
+    struct AlwaysValidScheme;
+
+    impl SignatureScheme for AlwaysValidScheme {
+        fn sign(&self, _signer: &PrivateKey, _hash: &H256) -> Option<BftSig> {
+            None
+        }
+
+        fn check_sig(&self, _signature: &[u8], _hash: &H256) -> Option<Address> {
+            None
+        }
+
+        fn verify_precommits(&self, commits: &HashMap<Address, Signature>, _hash: &H256) -> HashSet<Address> {
+            commits.keys().cloned().collect()
+        }
+    }
+
+    #[test]
+    fn test_verify_bft_proof_accepts_a_real_block_proof_pair() {
+        // Pin down the hash basis `check_block` actually uses: the full
+        // `Block` protobuf with its header proof cleared, not just the
+        // header or a `Proposal`/`CompactProposal` encoding.
+        let mut block = Block::new();
+        block.mut_header().set_height(10);
+        block.mut_header().set_round(0);
+
+        let mut unsigned_block = block.clone();
+        unsigned_block.mut_header().clear_proof();
+        let block_bytes: Vec<u8> = unsigned_block.try_into().unwrap();
+        let block_hash = block_bytes.crypt_hash();
+
+        let voter = vec![1u8; 20];
+        let authority_list = vec![Node {
+            address: voter.clone(),
+            proposal_weight: 1,
+            vote_weight: 100,
+        }];
+        let mut commits = HashMap::new();
+        commits.insert(voter, Signature::from(&[0u8; SIGNATURE_BYTES_LEN][..]));
+        let bft_proof = BftProof {
+            proposal: block_hash.clone(),
+            height: 10,
+            round: 0,
+            commits,
+        };
+
+        assert!(verify_bft_proof(&bft_proof, &block_hash, 10, 0, &authority_list, &AlwaysValidScheme));
+    }
+
+    #[test]
+    fn test_schnorr_verify_precommits_rejects_forged_vote() {
+        let good_addr = Address::from(&[1u8; 20][..]);
+        let bad_addr = Address::from(&[2u8; 20][..]);
+
+        let secret = Scalar::from(42u64);
+        let vk = &secret * &RISTRETTO_BASEPOINT_TABLE;
+        let forged_vk = &Scalar::from(99u64) * &RISTRETTO_BASEPOINT_TABLE;
+
+        let mut registry = HashMap::new();
+        registry.insert(good_addr.clone(), vk);
+        registry.insert(bad_addr.clone(), forged_vk);
+        let scheme = SchnorrScheme::new(registry);
+
+        let hash = H256::from(&[7u8; 32][..]);
+
+        let nonce = Scalar::from(7u64);
+        let r = &nonce * &RISTRETTO_BASEPOINT_TABLE;
+        let c = SchnorrScheme::challenge(&r, &vk, &hash);
+        let s = nonce + c * secret;
+        let mut good_bytes = Vec::with_capacity(SIGNATURE_BYTES_LEN);
+        good_bytes.extend_from_slice(r.compress().as_bytes());
+        good_bytes.extend_from_slice(s.as_bytes());
+        good_bytes.resize(SIGNATURE_BYTES_LEN, 0);
+        let good_sig = Signature::from(&good_bytes[..]);
+
+        // Same shape as a real vote, but `s` doesn't satisfy the Schnorr
+        // equation for `forged_vk` -- a vote that was never honestly signed.
+        let forged_r = &Scalar::from(3u64) * &RISTRETTO_BASEPOINT_TABLE;
+        let forged_s = Scalar::from(1u64);
+        let mut forged_bytes = Vec::with_capacity(SIGNATURE_BYTES_LEN);
+        forged_bytes.extend_from_slice(forged_r.compress().as_bytes());
+        forged_bytes.extend_from_slice(forged_s.as_bytes());
+        forged_bytes.resize(SIGNATURE_BYTES_LEN, 0);
+        let forged_sig = Signature::from(&forged_bytes[..]);
+
+        let mut commits = HashMap::new();
+        commits.insert(good_addr.clone(), good_sig);
+        commits.insert(bad_addr.clone(), forged_sig);
+
+        let verified = scheme.verify_precommits(&commits, &hash);
+
+        assert!(verified.contains(&good_addr));
+        assert!(!verified.contains(&bad_addr));
+    }
+
+    #[test]
+    fn test_drain_resolved_check_tx_answers_stale_and_pending_in_order() {
+        let mut check_tx_reqs: VecDeque<(u64, u64)> = VecDeque::new();
+        check_tx_reqs.push_back((1, 0)); // stale: below current_height
+        check_tx_reqs.push_back((2, 0)); // resolved: a response is buffered
+        check_tx_reqs.push_back((3, 0)); // still pending: no response yet
+
+        let mut check_tx_resps = HashMap::new();
+        let mut resp = VerifyBlockResp::new();
+        resp.set_pass(true);
+        check_tx_resps.insert((2, 0), resp);
+
+        let replies = drain_resolved_check_tx(&mut check_tx_reqs, &mut check_tx_resps, 2);
+
+        assert_eq!(replies, vec![false, true]);
+        assert_eq!(check_tx_reqs.len(), 1);
+        assert_eq!(check_tx_reqs.front(), Some(&(3, 0)));
+    }
 }
\ No newline at end of file